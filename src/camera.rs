@@ -0,0 +1,72 @@
+use crate::geometry::Vec3f;
+
+/// Returns a random point in the unit disk (z = 0), via rejection sampling.
+fn random_in_unit_disk() -> Vec3f {
+    loop {
+        let p = Vec3f::new(
+            2. * rand::random::<f32>() - 1.,
+            2. * rand::random::<f32>() - 1.,
+            0.,
+        );
+        if p * p < 1. {
+            return p;
+        }
+    }
+}
+
+/// A positionable camera with an optional thin lens, for defocus (depth-of-field) blur.
+pub struct Camera {
+    origin: Vec3f,
+    lower_left_corner: Vec3f,
+    horizontal: Vec3f,
+    vertical: Vec3f,
+    u: Vec3f,
+    v: Vec3f,
+    lens_radius: f32,
+}
+
+impl Camera {
+    /// `vfov` is the vertical field of view, in radians. `focus_dist` is the distance
+    /// from `lookfrom` to the plane that is in perfect focus.
+    pub fn new(
+        lookfrom: Vec3f,
+        lookat: Vec3f,
+        vup: Vec3f,
+        vfov: f32,
+        aspect: f32,
+        aperture: f32,
+        focus_dist: f32,
+    ) -> Self {
+        let half_height = (vfov / 2.).tan();
+        let half_width = aspect * half_height;
+
+        let w = (lookfrom - lookat).normalized();
+        let u = vup.cross(w).normalized();
+        let v = w.cross(u);
+
+        let lower_left_corner = lookfrom
+            - u * (half_width * focus_dist)
+            - v * (half_height * focus_dist)
+            - w * focus_dist;
+
+        Camera {
+            origin: lookfrom,
+            lower_left_corner,
+            horizontal: u * (2. * half_width * focus_dist),
+            vertical: v * (2. * half_height * focus_dist),
+            u,
+            v,
+            lens_radius: aperture / 2.,
+        }
+    }
+
+    /// Returns the `(origin, direction)` of the ray through screen coordinates `(s, t)`,
+    /// where `s` and `t` range from 0 to 1 across the image plane.
+    pub fn get_ray(&self, s: f32, t: f32) -> (Vec3f, Vec3f) {
+        let lens = random_in_unit_disk() * self.lens_radius;
+        let offset = self.u * lens.x + self.v * lens.y;
+        let origin = self.origin + offset;
+        let dir = self.lower_left_corner + self.horizontal * s + self.vertical * t - origin;
+        (origin, dir)
+    }
+}
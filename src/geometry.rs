@@ -1,4 +1,11 @@
-use std::ops::{Add, Mul, Sub};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+pub(crate) fn lerp<T>(v0: T, v1: T, t: f32) -> T
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T> + Copy,
+{
+    v0 + (v1 - v0) * t.min(1.).max(0.)
+}
 
 #[derive(Copy, Clone)]
 pub struct Vec3f {
@@ -12,15 +19,64 @@ impl Vec3f {
         Vec3f { x, y, z }
     }
 
+    pub fn dot(&self, rhs: Vec3f) -> f32 {
+        *self * rhs
+    }
+
+    pub fn cross(&self, rhs: Vec3f) -> Vec3f {
+        Vec3f::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x,
+        )
+    }
+
+    pub fn length_squared(&self) -> f32 {
+        self.dot(*self)
+    }
+
     pub fn norm(&self) -> f32 {
-        let Self { x, y, z } = *self;
-        (x * x + y * y + z * z).sqrt()
+        self.length_squared().sqrt()
     }
 
     pub fn normalize(&mut self) -> Self {
         *self = (*self) * (1. / self.norm());
         *self
     }
+
+    /// Like [`Vec3f::normalize`], but returns a new vector instead of mutating `self`.
+    pub fn normalized(&self) -> Self {
+        *self * (1. / self.norm())
+    }
+
+    /// Reflects `self` (an incident direction) off a surface with the given `normal`.
+    pub fn reflect(&self, normal: Vec3f) -> Vec3f {
+        *self - normal * 2. * self.dot(normal)
+    }
+
+    /// Refracts `self` (an incident direction) through a surface with the given
+    /// `normal`, going from a medium of refractive index `eta_i` into one of
+    /// `eta_t`. Returns `None` on total internal reflection.
+    pub fn refract(&self, normal: Vec3f, eta_i: f32, eta_t: f32) -> Option<Vec3f> {
+        let mut cos_i = -self.dot(normal).clamp(-1., 1.);
+        let mut n = normal;
+        let mut eta_i = eta_i;
+        let mut eta_t = eta_t;
+        if cos_i < 0. {
+            // The ray is leaving the surface rather than entering it.
+            cos_i = -cos_i;
+            std::mem::swap(&mut eta_i, &mut eta_t);
+            n = n * -1.;
+        }
+
+        let eta = eta_i / eta_t;
+        let k = 1. - eta * eta * (1. - cos_i * cos_i);
+        if k < 0. {
+            None
+        } else {
+            Some(*self * eta + n * (eta * cos_i - k.sqrt()))
+        }
+    }
 }
 
 impl Add for Vec3f {
@@ -57,3 +113,17 @@ impl Mul for Vec3f {
         self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
     }
 }
+
+impl Div<f32> for Vec3f {
+    type Output = Self;
+    fn div(self, rhs: f32) -> Self {
+        Vec3f::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl Neg for Vec3f {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Vec3f::new(-self.x, -self.y, -self.z)
+    }
+}
@@ -0,0 +1,23 @@
+use crate::geometry::Vec3f;
+
+/// How a surface point looks and behaves under lighting: its Lambertian diffuse
+/// color, a Phong specular highlight, and how much of a reflection ray's color to
+/// mix in.
+#[derive(Copy, Clone)]
+pub struct Material {
+    pub diffuse: Vec3f,
+    pub specular: Vec3f,
+    pub specular_exponent: f32,
+    pub reflectivity: f32,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material {
+            diffuse: Vec3f::new(1., 1., 1.),
+            specular: Vec3f::new(0., 0., 0.),
+            specular_exponent: 1.,
+            reflectivity: 0.,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::geometry::{lerp, Vec3f};
+
+fn hash(n: f32) -> f32 {
+    let x = n.sin() * 43758.5453;
+    x - x.floor()
+}
+
+fn noise(x: Vec3f) -> f32 {
+    let p = Vec3f::new(x.x.floor(), x.y.floor(), x.z.floor());
+    let f = Vec3f::new(x.x - p.x, x.y - p.y, x.z - p.z);
+    let f = f * (f * (Vec3f::new(3., 3., 3.) - f * 2.));
+    let n = p * Vec3f::new(1., 57., 113.);
+
+    lerp(
+        lerp(
+            lerp(hash(n + 0.), hash(n + 1.), f.x),
+            lerp(hash(n + 57.), hash(n + 58.), f.x),
+            f.y,
+        ),
+        lerp(
+            lerp(hash(n + 113.), hash(n + 114.), f.x),
+            lerp(hash(n + 170.), hash(n + 171.), f.x),
+            f.y,
+        ),
+        f.z,
+    )
+}
+
+fn rotate(v: Vec3f) -> Vec3f {
+    Vec3f::new(
+        Vec3f::new(0.00, 0.80, 0.60) * v,
+        Vec3f::new(-0.80, 0.36, -0.48) * v,
+        Vec3f::new(-0.60, -0.48, 0.64) * v,
+    )
+}
+
+/// Four octaves of value noise, for the displaced-sphere fireball surface.
+pub(crate) fn fractal_brownian_motion(v: Vec3f) -> f32 {
+    let mut p = rotate(v);
+    let mut f = 0.0;
+    f += 0.5000 * noise(p);
+    p = p * 2.32;
+    f += 0.2500 * noise(p);
+    p = p * 3.03;
+    f += 0.1250 * noise(p);
+    p = p * 2.61;
+    f += 0.0625 * noise(p);
+
+    f / 0.9375
+}
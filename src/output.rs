@@ -0,0 +1,107 @@
+use crate::geometry::Vec3f;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Applies gamma-2 correction and quantizes a linear color channel to `0..=255`.
+fn quantize(channel: f32) -> u8 {
+    (channel.sqrt().clamp(0., 1.) * 255.) as u8
+}
+
+/// A framebuffer encoder. Implementors own the on-disk format; `main` only needs
+/// to pick one and hand it the rendered pixels.
+pub trait Output {
+    fn write(
+        &self,
+        framebuffer: &[Vec3f],
+        width: usize,
+        height: usize,
+        w: &mut dyn Write,
+    ) -> io::Result<()>;
+}
+
+/// Binary PPM (`P6`).
+pub struct P6;
+
+impl Output for P6 {
+    fn write(
+        &self,
+        framebuffer: &[Vec3f],
+        width: usize,
+        height: usize,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", width, height)?;
+        for pixel in framebuffer {
+            w.write_all(&[quantize(pixel.x), quantize(pixel.y), quantize(pixel.z)])?;
+        }
+        Ok(())
+    }
+}
+
+/// Human-readable ASCII PPM (`P3`).
+pub struct P3;
+
+impl Output for P3 {
+    fn write(
+        &self,
+        framebuffer: &[Vec3f],
+        width: usize,
+        height: usize,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        writeln!(w, "P3\n{} {}\n255", width, height)?;
+        for pixel in framebuffer {
+            writeln!(
+                w,
+                "{} {} {}",
+                quantize(pixel.x),
+                quantize(pixel.y),
+                quantize(pixel.z)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// PNG, via the `image` crate.
+pub struct Png;
+
+impl Output for Png {
+    fn write(
+        &self,
+        framebuffer: &[Vec3f],
+        width: usize,
+        height: usize,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(width * height * 3);
+        for pixel in framebuffer {
+            buf.extend_from_slice(&[quantize(pixel.x), quantize(pixel.y), quantize(pixel.z)]);
+        }
+
+        let image = image::RgbImage::from_raw(width as u32, height as u32, buf)
+            .expect("framebuffer has exactly width * height pixels");
+
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        w.write_all(&bytes)
+    }
+}
+
+/// Picks an [`Output`] backend from a file's extension, defaulting to binary PPM
+/// for anything that isn't recognized (so `out.ppm` and extension-less paths keep
+/// working the way they always did). `ascii` forces the human-readable `P3`
+/// backend regardless of extension, since there's no PPM extension convention
+/// that distinguishes ASCII from binary.
+pub fn backend_for_path(path: &Path, ascii: bool) -> Box<dyn Output> {
+    if ascii {
+        return Box::new(P3);
+    }
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => Box::new(Png),
+        _ => Box::new(P6),
+    }
+}
@@ -0,0 +1,260 @@
+use crate::geometry::{lerp, Vec3f};
+use crate::material::Material;
+use crate::noise::fractal_brownian_motion;
+
+/// A signed distance field: `distance(p)` is negative inside the surface, positive
+/// outside, and its magnitude is (an underestimate of) the distance to the surface.
+pub trait Sdf: Sync {
+    fn distance(&self, p: Vec3f) -> f32;
+
+    /// The material of the surface nearest `p`. `p` is assumed to already lie on
+    /// (or very near) the surface, as it does right after `intersect` finds a hit.
+    fn material(&self, _p: Vec3f) -> Material {
+        Material::default()
+    }
+
+    /// Finds the first point where the ray `origin + t*dir` (`dir` must be a unit
+    /// vector) hits this surface, within [`MAX_TRACE_DISTANCE`] (the default
+    /// implementation also gives up after 128 steps). The default implementation
+    /// sphere-marches through `distance`, which is correct for any SDF but, since
+    /// each step is scaled down to stay safe under noisy displacement, converges
+    /// slowly for unbounded surfaces seen at a grazing angle. Exact primitives like
+    /// [`Plane`] override this with a closed-form solution instead.
+    fn intersect(&self, origin: Vec3f, dir: Vec3f) -> Option<Vec3f> {
+        let mut pos = origin;
+        let mut traveled = 0.;
+        for _ in 0..128 {
+            let d = self.distance(pos);
+            if d < 0. {
+                return Some(pos);
+            }
+
+            let step = (d * 0.1).max(0.01);
+            pos = pos + dir * step;
+            traveled += step;
+            if traveled > MAX_TRACE_DISTANCE {
+                return None;
+            }
+        }
+        None
+    }
+}
+
+/// The marching budget for the default, distance-field-driven [`Sdf::intersect`].
+const MAX_TRACE_DISTANCE: f32 = 40.;
+
+pub struct Sphere {
+    pub center: Vec3f,
+    pub radius: f32,
+    pub material: Material,
+}
+
+impl Sdf for Sphere {
+    fn distance(&self, p: Vec3f) -> f32 {
+        (p - self.center).norm() - self.radius
+    }
+
+    fn material(&self, _p: Vec3f) -> Material {
+        self.material
+    }
+}
+
+/// An infinite plane through `center`, facing `normal` (which should be a unit vector).
+pub struct Plane {
+    pub center: Vec3f,
+    pub normal: Vec3f,
+    pub material: Material,
+}
+
+impl Sdf for Plane {
+    fn distance(&self, p: Vec3f) -> f32 {
+        (p - self.center) * self.normal
+    }
+
+    fn material(&self, _p: Vec3f) -> Material {
+        self.material
+    }
+
+    fn intersect(&self, origin: Vec3f, dir: Vec3f) -> Option<Vec3f> {
+        let denom = dir * self.normal;
+        if denom.abs() < 1e-6 {
+            return None;
+        }
+
+        let t = ((self.center - origin) * self.normal) / denom;
+        if t < 0. {
+            return None;
+        }
+
+        Some(origin + dir * t)
+    }
+}
+
+/// An axis-aligned box centered at `center` with the given `half_extents`.
+pub struct Cuboid {
+    pub center: Vec3f,
+    pub half_extents: Vec3f,
+    pub material: Material,
+}
+
+impl Sdf for Cuboid {
+    fn distance(&self, p: Vec3f) -> f32 {
+        let d = p - self.center;
+        let qx = d.x.abs() - self.half_extents.x;
+        let qy = d.y.abs() - self.half_extents.y;
+        let qz = d.z.abs() - self.half_extents.z;
+
+        let outside = Vec3f::new(qx.max(0.), qy.max(0.), qz.max(0.)).norm();
+        let inside = qx.max(qy).max(qz).min(0.);
+        outside + inside
+    }
+
+    fn material(&self, _p: Vec3f) -> Material {
+        self.material
+    }
+}
+
+fn palette_fire(d: f32) -> Vec3f {
+    let yellow = Vec3f::new(1.7, 1.3, 1.0);
+    let orange = Vec3f::new(1.0, 0.6, 0.0);
+    let red = Vec3f::new(1.0, 0.0, 0.0);
+    let darkgray = Vec3f::new(0.2, 0.2, 0.2);
+    let gray = Vec3f::new(0.4, 0.4, 0.4);
+
+    let d = d.min(1.).max(0.);
+    if d < 0.25 {
+        lerp(gray, darkgray, d * 4.)
+    } else if d < 0.5 {
+        lerp(darkgray, red, d * 4. - 1.)
+    } else if d < 0.75 {
+        lerp(red, orange, d * 4. - 2.)
+    } else {
+        lerp(orange, yellow, d * 4. - 3.)
+    }
+}
+
+/// The noisy, fractal-brownian-motion-displaced sphere the fireball is built from.
+pub struct Fireball {
+    pub center: Vec3f,
+    pub radius: f32,
+    pub amplitude: f32,
+}
+
+impl Sdf for Fireball {
+    fn distance(&self, p: Vec3f) -> f32 {
+        let p = p - self.center;
+        let displacement = -fractal_brownian_motion(p * 3.4) * self.amplitude;
+        p.norm() - (self.radius + displacement)
+    }
+
+    fn material(&self, p: Vec3f) -> Material {
+        let p = p - self.center;
+        let noise_level = (self.radius - p.norm()) / self.amplitude;
+        Material {
+            diffuse: palette_fire((-0.2 + noise_level) * 2.),
+            specular: Vec3f::new(1., 1., 1.),
+            specular_exponent: 30.,
+            reflectivity: 0.,
+        }
+    }
+}
+
+pub struct Union(pub Box<dyn Sdf>, pub Box<dyn Sdf>);
+
+impl Sdf for Union {
+    fn distance(&self, p: Vec3f) -> f32 {
+        self.0.distance(p).min(self.1.distance(p))
+    }
+
+    fn material(&self, p: Vec3f) -> Material {
+        if self.0.distance(p) <= self.1.distance(p) {
+            self.0.material(p)
+        } else {
+            self.1.material(p)
+        }
+    }
+
+    fn intersect(&self, origin: Vec3f, dir: Vec3f) -> Option<Vec3f> {
+        match (self.0.intersect(origin, dir), self.1.intersect(origin, dir)) {
+            (Some(a), Some(b)) => {
+                if (a - origin).length_squared() <= (b - origin).length_squared() {
+                    Some(a)
+                } else {
+                    Some(b)
+                }
+            }
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+pub struct Intersection(pub Box<dyn Sdf>, pub Box<dyn Sdf>);
+
+impl Sdf for Intersection {
+    fn distance(&self, p: Vec3f) -> f32 {
+        self.0.distance(p).max(self.1.distance(p))
+    }
+
+    fn material(&self, p: Vec3f) -> Material {
+        if self.0.distance(p) >= self.1.distance(p) {
+            self.0.material(p)
+        } else {
+            self.1.material(p)
+        }
+    }
+}
+
+/// `self.0` with `self.1` carved out of it.
+pub struct Subtraction(pub Box<dyn Sdf>, pub Box<dyn Sdf>);
+
+impl Sdf for Subtraction {
+    fn distance(&self, p: Vec3f) -> f32 {
+        self.0.distance(p).max(-self.1.distance(p))
+    }
+
+    fn material(&self, p: Vec3f) -> Material {
+        if self.0.distance(p) >= -self.1.distance(p) {
+            self.0.material(p)
+        } else {
+            self.1.material(p)
+        }
+    }
+}
+
+/// The blend weight for a polynomial smooth union of two surfaces with
+/// distances `da`, `db`, over a radius `k`: 1 where `a` dominates, 0 where `b`
+/// dominates, smoothly in between.
+fn smoothstep_weight(da: f32, db: f32, k: f32) -> f32 {
+    (0.5 + 0.5 * (db - da) / k).clamp(0., 1.)
+}
+
+/// A polynomial smooth union: blends `a` and `b` into one surface over a
+/// radius `k`, instead of the hard edge a plain [`Union`] leaves where they meet.
+pub struct SmoothUnion {
+    pub a: Box<dyn Sdf>,
+    pub b: Box<dyn Sdf>,
+    pub k: f32,
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, p: Vec3f) -> f32 {
+        let da = self.a.distance(p);
+        let db = self.b.distance(p);
+        let h = smoothstep_weight(da, db, self.k);
+        lerp(db, da, h) - self.k * h * (1. - h)
+    }
+
+    fn material(&self, p: Vec3f) -> Material {
+        let da = self.a.distance(p);
+        let db = self.b.distance(p);
+        let h = smoothstep_weight(da, db, self.k);
+        let ma = self.a.material(p);
+        let mb = self.b.material(p);
+        Material {
+            diffuse: lerp(mb.diffuse, ma.diffuse, h),
+            specular: lerp(mb.specular, ma.specular, h),
+            specular_exponent: lerp(mb.specular_exponent, ma.specular_exponent, h),
+            reflectivity: lerp(mb.reflectivity, ma.reflectivity, h),
+        }
+    }
+}